@@ -1,8 +1,10 @@
 //! Lazy-copying lazy-allocated scanning [`str`] transformations.  
 //! This is good e.g. for (un)escaping text, especially if individual strings are short.
 //!
-//! Note that this library uses [smartstring] (and as such returns [`Woc`]s instead of [`Cow`]s).  
+//! Note that this library uses [smartstring] by default (and as such returns [`Woc`]s instead of [`Cow`]s).
 //! The output is still [`Deref<Target = str>`] regardless, so there should be no issue with ease of use.
+//! [`transform`], [`try_transform`] and the [`Transform`] methods are generic over the output buffer via [`OutputString`], so [`std::string::String`] works there too.
+//! [smartstring] remains a dependency either way, and the provided functions ([`escape_double_quotes`] etc.) always return its `String`.
 //!
 //! # Example
 //!
@@ -41,19 +43,47 @@ pub mod readme {
 use cervine::Cow;
 use gnaw::Unshift as _;
 use smartstring::alias::String;
+use std::borrow::Borrow;
+use std::convert::Infallible;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// The output buffer type that [`TransformedPart`] defaults to and that this crate's provided functions (e.g. [`escape_double_quotes`]) return.
+pub type DefaultString = String;
+
+/// Helper trait for owned string types that [`transform`]/[`Transform::transform`] (and their fallible and default-string-backed siblings) can accumulate their output into.
+///
+/// Implemented for [`std::string::String`] and for [smartstring]'s [`alias::String`](`smartstring::alias::String`), the latter being this crate's [`DefaultString`].
+pub trait OutputString: Default + for<'a> From<&'a str> + AsRef<str> + Borrow<str> {
+	/// Appends the given [`str`] slice onto the end of this string.
+	fn push_str(&mut self, string: &str);
+}
+
+impl OutputString for std::string::String {
+	fn push_str(&mut self, string: &str) {
+		std::string::String::push_str(self, string);
+	}
+}
+
+impl OutputString for String {
+	fn push_str(&mut self, string: &str) {
+		String::push_str(self, string);
+	}
+}
 
 /// Inidicates whether the consumed part of the input remains unchanged or is to be replaced.
-pub enum TransformedPart {
+pub enum TransformedPart<S = DefaultString> {
 	Unchanged,
-	Changed(String),
+	Changed(S),
 }
 
 /// Transforms the given `str` according to `transform_next` as lazily as possible.
 ///
-/// With each invocation, `transform_next` should consume part of the input (by slicing its parameter in place) and return a replacement [`String`] if necessary.
+/// With each invocation, `transform_next` should consume part of the input (by slicing its parameter in place) and return a replacement `S` if necessary.
 /// `transform` returns once the input is an empty [`str`].
 ///
-/// [`String`]: https://doc.rust-lang.org/stable/std/string/struct.String.html
+/// `S` is inferred from `transform_next`'s return type, so existing callers that build [`TransformedPart`]s from [smartstring]'s `String` don't need to change anything. Note that `S` has no true default here: [`TransformedPart`]'s own `S = DefaultString` default parameter isn't picked up by inference on a free function, so a `transform_next` that never constructs a concretely-typed [`TransformedPart::Changed`] (e.g. one that always returns [`TransformedPart::Unchanged`]) needs an explicit turbofish, e.g. `transform::<DefaultString>(..)`. Pass e.g. `transform::<std::string::String>(..)` (or let a `TransformedPart<std::string::String>` returned from `transform_next` pin it) to opt into the standard library's string instead.
+///
 /// [`str`]: https://doc.rust-lang.org/stable/std/primitive.str.html
 ///
 /// # Example
@@ -78,13 +108,73 @@ pub enum TransformedPart {
 ///
 /// assert_eq!(output, Cow::Owned(r#"a \"quoted\" word"#.into()));
 /// ```
-pub fn transform(
+///
+/// Opting into [`std::string::String`] as the output buffer:
+///
+/// ```rust
+/// use cervine::Cow;
+/// use lazy_transform_str::{transform, TransformedPart};
+///
+/// let output = transform::<std::string::String>("abc", |rest| {
+///     *rest = &rest[1..];
+///     TransformedPart::Changed(std::string::String::from("x"))
+/// });
+///
+/// assert_eq!(output, Cow::Owned(std::string::String::from("xxx")));
+/// ```
+pub fn transform<S: OutputString>(
 	str: &str,
-	transform_next: impl FnMut(/* rest: */ &mut &str) -> TransformedPart,
-) -> Cow<String, str> {
+	transform_next: impl FnMut(/* rest: */ &mut &str) -> TransformedPart<S>,
+) -> Cow<S, str> {
 	str.transform(transform_next)
 }
 
+/// Transforms the given `str` according to `transform_next` as lazily as possible, aborting on the first error.
+///
+/// With each invocation, `transform_next` should consume part of the input (by slicing its parameter in place) and return a replacement `S` if necessary.
+/// `try_transform` returns once the input is an empty [`str`] or `transform_next` returns [`Err`], whichever happens first.
+///
+/// [`str`]: https://doc.rust-lang.org/stable/std/primitive.str.html
+///
+/// # Errors
+///
+/// Iff `transform_next` returns [`Err`]. The partially transformed output is discarded in that case.
+///
+/// # Example
+///
+/// ```rust
+/// use cervine::Cow;
+/// use gnaw::Unshift as _;
+/// use lazy_transform_str::{try_transform, TransformedPart};
+/// use smartstring::alias::String;
+///
+/// fn unescape_hex(input: &str) -> Result<Cow<String, str>, &'static str> {
+///     try_transform(input, |rest| match rest.unshift().unwrap() {
+///         '\\' => {
+///             if rest.len() < 2 {
+///                 return Err("truncated escape sequence");
+///             }
+///             let (digits, tail) = rest.split_at(2);
+///             *rest = tail;
+///             let byte = u8::from_str_radix(digits, 16).map_err(|_| "invalid hex digits")?;
+///             let mut changed = String::new();
+///             changed.push(byte as char);
+///             Ok(TransformedPart::Changed(changed))
+///         }
+///         _ => Ok(TransformedPart::Unchanged),
+///     })
+/// }
+///
+/// assert_eq!(unescape_hex(r"a\41b"), Ok(Cow::Owned("aAb".into())));
+/// assert_eq!(unescape_hex(r"a\4"), Err("truncated escape sequence"));
+/// ```
+pub fn try_transform<S: OutputString, E>(
+	str: &str,
+	transform_next: impl FnMut(/* rest: */ &mut &str) -> Result<TransformedPart<S>, E>,
+) -> Result<Cow<S, str>, E> {
+	str.try_transform(transform_next)
+}
+
 /// Helper trait to call [`transform`] as method on [`&str`].
 ///
 /// [`transform`]: ./fn.transform.html
@@ -113,41 +203,254 @@ pub fn transform(
 /// assert_eq!(output, Cow::Owned(r#"a \"quoted\" word"#.into()));
 /// ```
 pub trait Transform {
-	fn transform(
+	fn transform<S: OutputString>(
 		&self,
-		transform_next: impl FnMut(&mut &str) -> TransformedPart,
-	) -> Cow<String, str>;
+		transform_next: impl FnMut(&mut &str) -> TransformedPart<S>,
+	) -> Cow<S, str>;
+
+	/// Fallible variant of [`transform`](`Transform::transform`) that aborts as soon as `transform_next` returns [`Err`].
+	///
+	/// # Errors
+	///
+	/// Iff `transform_next` returns [`Err`]. The partially transformed output is discarded in that case.
+	fn try_transform<S: OutputString, E>(
+		&self,
+		transform_next: impl FnMut(&mut &str) -> Result<TransformedPart<S>, E>,
+	) -> Result<Cow<S, str>, E>;
+
+	/// Streaming variant of [`transform`](`Transform::transform`) that writes straight into `sink` instead of building an owned string.
+	///
+	/// # Errors
+	///
+	/// Iff writing to `sink` fails.
+	fn transform_to<S: AsRef<str>, W: fmt::Write>(
+		&self,
+		sink: &mut W,
+		transform_next: impl FnMut(&mut &str) -> TransformedPart<S>,
+	) -> fmt::Result;
+
+	/// Lazy, pull-based variant of [`transform`](`Transform::transform`): returns an iterator that yields the borrowed or [`TransformedPart::Changed`] chunk produced by each `transform_next` call, as it is produced.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use cervine::Cow;
+	/// use lazy_transform_str::{Transform as _, TransformedPart};
+	///
+	/// let input = r#"a "quoted" word"#;
+	///
+	/// // Escape double quotes, consuming whole unquoted runs at once.
+	/// let chunks: Vec<_> = input
+	///     .transform_chunks(|rest| {
+	///         if let Some(quoted) = rest.strip_prefix('"') {
+	///             *rest = quoted;
+	///             TransformedPart::Changed(String::from(r#"\""#))
+	///         } else {
+	///             let end = rest.find('"').unwrap_or(rest.len());
+	///             let (_, tail) = rest.split_at(end);
+	///             *rest = tail;
+	///             TransformedPart::Unchanged
+	///         }
+	///     })
+	///     .collect();
+	///
+	/// assert_eq!(
+	///     chunks,
+	///     vec![
+	///         Cow::Borrowed("a "),
+	///         Cow::Owned(String::from(r#"\""#)),
+	///         Cow::Borrowed("quoted"),
+	///         Cow::Owned(String::from(r#"\""#)),
+	///         Cow::Borrowed(" word"),
+	///     ],
+	/// );
+	/// ```
+	fn transform_chunks<S, F: FnMut(&mut &str) -> TransformedPart<S>>(
+		&self,
+		transform_next: F,
+	) -> TransformChunks<'_, S, F>;
 }
 
 impl Transform for str {
-	fn transform(
+	fn transform<S: OutputString>(
 		&self,
-		mut transform_next: impl FnMut(&mut &str) -> TransformedPart,
-	) -> Cow<String, str> {
+		mut transform_next: impl FnMut(&mut &str) -> TransformedPart<S>,
+	) -> Cow<S, str> {
+		match self.try_transform::<S, Infallible>(|rest| Ok(transform_next(rest))) {
+			Ok(transformed) => transformed,
+			Err(infallible) => match infallible {},
+		}
+	}
+
+	fn try_transform<S: OutputString, E>(
+		&self,
+		mut transform_next: impl FnMut(&mut &str) -> Result<TransformedPart<S>, E>,
+	) -> Result<Cow<S, str>, E> {
 		let mut rest = self;
 		let mut copied = loop {
 			if rest.is_empty() {
-				return Cow::Borrowed(self);
+				return Ok(Cow::Borrowed(self));
 			}
 			let unchanged_rest = rest;
-			if let TransformedPart::Changed(transformed) = transform_next(&mut rest) {
-				let mut copied = String::from(&self[..self.len() - unchanged_rest.len()]);
-				copied.push_str(&transformed);
+			if let TransformedPart::Changed(transformed) = transform_next(&mut rest)? {
+				let mut copied = S::from(&self[..self.len() - unchanged_rest.len()]);
+				copied.push_str(transformed.as_ref());
 				break copied;
 			}
 		};
 
 		while !rest.is_empty() {
 			let unchanged_rest = rest;
-			match transform_next(&mut rest) {
+			match transform_next(&mut rest)? {
 				TransformedPart::Unchanged => {
 					copied.push_str(&unchanged_rest[..unchanged_rest.len() - rest.len()]);
 				}
-				TransformedPart::Changed(changed) => copied.push_str(&changed),
+				TransformedPart::Changed(changed) => copied.push_str(changed.as_ref()),
+			}
+		}
+
+		Ok(Cow::Owned(copied))
+	}
+
+	fn transform_to<S: AsRef<str>, W: fmt::Write>(
+		&self,
+		sink: &mut W,
+		mut transform_next: impl FnMut(&mut &str) -> TransformedPart<S>,
+	) -> fmt::Result {
+		let mut rest = self;
+		while !rest.is_empty() {
+			let unchanged_rest = rest;
+			match transform_next(&mut rest) {
+				TransformedPart::Unchanged => {
+					sink.write_str(&unchanged_rest[..unchanged_rest.len() - rest.len()])?;
+				}
+				TransformedPart::Changed(changed) => sink.write_str(changed.as_ref())?,
 			}
 		}
+		Ok(())
+	}
+
+	fn transform_chunks<S, F: FnMut(&mut &str) -> TransformedPart<S>>(
+		&self,
+		transform_next: F,
+	) -> TransformChunks<'_, S, F> {
+		TransformChunks {
+			rest: self,
+			transform_next,
+			marker: PhantomData,
+		}
+	}
+}
+
+/// Iterator returned by [`Transform::transform_chunks`]: yields the borrowed slice consumed by each [`TransformedPart::Unchanged`] call and the owned string of each [`TransformedPart::Changed`] call, in order, one `transform_next` call per [`next`](`Iterator::next`).
+pub struct TransformChunks<'a, S, F> {
+	rest: &'a str,
+	transform_next: F,
+	marker: PhantomData<fn() -> S>,
+}
+
+impl<'a, S, F: FnMut(&mut &str) -> TransformedPart<S>> Iterator for TransformChunks<'a, S, F> {
+	type Item = Cow<'a, S, str>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.rest.is_empty() {
+			return None;
+		}
+		let unchanged_rest = self.rest;
+		Some(match (self.transform_next)(&mut self.rest) {
+			TransformedPart::Unchanged => {
+				Cow::Borrowed(&unchanged_rest[..unchanged_rest.len() - self.rest.len()])
+			}
+			TransformedPart::Changed(changed) => Cow::Owned(changed),
+		})
+	}
+}
+
+/// Transforms the given `str` according to `transform_next` as lazily as possible, writing the result straight into `sink` instead of building an owned [`TransformedPart::Changed`]-accumulating buffer.
+///
+/// This is the streaming counterpart of [`transform`]: it's the better choice for very large inputs, or when the result is going straight into a [`fmt::Formatter`], a [`String`](std::string::String), or some other [`fmt::Write`] sink anyway.
+///
+/// # Errors
+///
+/// Iff writing to `sink` fails.
+///
+/// # Example
+///
+/// ```rust
+/// use gnaw::Unshift as _;
+/// use lazy_transform_str::{transform_to, TransformedPart};
+///
+/// let input = r#"a "quoted" word"#;
+///
+/// let mut output = String::new();
+/// transform_to(input, &mut output, |rest| match rest.unshift().unwrap() {
+///     c @ '\\' | c @ '"' => {
+///         let mut changed = String::from(r"\");
+///         changed.push(c);
+///         TransformedPart::Changed(changed)
+///     }
+///     _ => TransformedPart::Unchanged,
+/// })
+/// .unwrap();
+///
+/// assert_eq!(output, r#"a \"quoted\" word"#);
+/// ```
+pub fn transform_to<S: AsRef<str>, W: fmt::Write>(
+	str: &str,
+	sink: &mut W,
+	transform_next: impl FnMut(&mut &str) -> TransformedPart<S>,
+) -> fmt::Result {
+	str.transform_to(sink, transform_next)
+}
+
+/// Lazily scans and rewrites `str` each time it is formatted, by calling `transform_next_factory` to obtain a fresh scan callback for that pass, so that `format!`/`write!`/`println!` never allocate an intermediate owned string.
+///
+/// `transform_next_factory` is called once per [`fmt::Display::fmt`] invocation, which lets the resulting callback keep its own local state (as e.g. [`unescape_backslashed_verbatim`] does) without needing interior mutability.
+///
+/// # Example
+///
+/// ```rust
+/// use gnaw::Unshift as _;
+/// use lazy_transform_str::{lazy_transform_display, TransformedPart};
+///
+/// let input = r#"a "quoted" word"#;
+///
+/// let display = lazy_transform_display(input, || {
+///     |rest: &mut &str| match rest.unshift().unwrap() {
+///         c @ '\\' | c @ '"' => {
+///             let mut changed = String::from(r"\");
+///             changed.push(c);
+///             TransformedPart::Changed(changed)
+///         }
+///         _ => TransformedPart::Unchanged,
+///     }
+/// });
+///
+/// assert_eq!(format!("{}", display), r#"a \"quoted\" word"#);
+/// ```
+pub fn lazy_transform_display<S: AsRef<str>, C: FnMut(&mut &str) -> TransformedPart<S>>(
+	str: &str,
+	transform_next_factory: impl Fn() -> C,
+) -> TransformDisplay<'_, S, impl Fn() -> C> {
+	TransformDisplay {
+		str,
+		transform_next_factory,
+		marker: PhantomData,
+	}
+}
+
+/// Return type of [`lazy_transform_display`]; see there for details.
+pub struct TransformDisplay<'a, S, F> {
+	str: &'a str,
+	transform_next_factory: F,
+	marker: PhantomData<fn() -> S>,
+}
 
-		Cow::Owned(copied)
+impl<'a, S: AsRef<str>, C: FnMut(&mut &str) -> TransformedPart<S>, F: Fn() -> C> fmt::Display
+	for TransformDisplay<'a, S, F>
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.str.transform_to(f, (self.transform_next_factory)())
 	}
 }
 
@@ -212,3 +515,72 @@ pub fn unescape_backslashed_verbatim(string: &str) -> Cow<String, str> {
 		}
 	})
 }
+
+/// Replaces every run of consecutive [`char::is_whitespace`] characters in `string` with a single `' '`, as lazily as possible.
+///
+/// # Panics
+///
+/// Never. `rest` is never empty when `transform_next` is called, so the internal [`Unshift::unshift`](`gnaw::Unshift::unshift`) calls always succeed.
+///
+/// # Example
+///
+/// ```rust
+/// use cervine::Cow;
+/// use lazy_transform_str::collapse_whitespace;
+/// use smartstring::alias::String;
+///
+/// let input = "a\t\nb   c";
+///
+/// let output = collapse_whitespace(input);
+///
+/// assert_eq!(output, Cow::Owned("a b c".into()));
+/// assert_eq!(collapse_whitespace("a b c"), Cow::<String, str>::Borrowed("a b c"));
+/// ```
+#[must_use = "pure function"]
+pub fn collapse_whitespace(string: &str) -> Cow<String, str> {
+	string.transform(|rest| {
+		let first = rest.unshift().unwrap();
+		if !first.is_whitespace() {
+			return TransformedPart::Unchanged;
+		}
+		let mut run_len = 1;
+		while rest.chars().next().is_some_and(char::is_whitespace) {
+			rest.unshift().unwrap();
+			run_len += 1;
+		}
+		if run_len == 1 && first == ' ' {
+			TransformedPart::Unchanged
+		} else {
+			TransformedPart::Changed(String::from(" "))
+		}
+	})
+}
+
+/// Converts `string` into a valid HTML/anchor id (slug): lowercase alphanumeric [`char`]s pass through, ASCII whitespace becomes `'-'`, and every other [`char`] is dropped.
+///
+/// [`char`]: https://doc.rust-lang.org/stable/std/primitive.char.html
+///
+/// # Panics
+///
+/// Never. `rest` is never empty when `transform_next` is called, so the internal [`Unshift::unshift`](`gnaw::Unshift::unshift`) call always succeeds.
+///
+/// # Example
+///
+/// ```rust
+/// use cervine::Cow;
+/// use lazy_transform_str::normalize_html_id;
+///
+/// let input = "Hello, World!";
+///
+/// let output = normalize_html_id(input);
+///
+/// assert_eq!(output, Cow::Owned("ello-orld".into()));
+/// ```
+#[must_use = "pure function"]
+pub fn normalize_html_id(string: &str) -> Cow<String, str> {
+	string.transform(|rest| match rest.unshift().unwrap() {
+		c if c.is_alphanumeric() && !c.is_uppercase() => TransformedPart::Unchanged,
+		c if c.is_ascii_whitespace() => TransformedPart::Changed(String::from("-")),
+		_ => TransformedPart::Changed(String::new()),
+	})
+}